@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{Container, Group, JobConfig, Service};
+
+#[derive(Debug, Serialize)]
+pub struct ComposeFile {
+    pub version: String,
+    pub services: BTreeMap<String, ComposeService>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub volumes: BTreeMap<String, ComposeVolume>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComposeService {
+    pub image: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<ComposeHealthcheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComposeHealthcheck {
+    pub test: Vec<String>,
+    pub interval: String,
+    pub timeout: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComposeVolume {}
+
+fn env_value_to_string(val: &toml::Value) -> String {
+    if let Some(s) = val.as_str() {
+        s.to_string()
+    } else {
+        val.to_string()
+    }
+}
+
+fn resolve_port(label: &str, group: &Group) -> Option<u16> {
+    group.ports.iter().find(|p| p.name == label).map(|p| p.to)
+}
+
+/// Maps a container's `Port` labels (e.g. `"http"`) to compose `"host:container"`
+/// mappings by looking each one up in the group's `[[ports]]` table.
+fn compose_ports(container: &Container, group: &Group) -> Vec<String> {
+    container
+        .ports
+        .iter()
+        .filter_map(|label| resolve_port(label, group))
+        .map(|to| format!("{to}:{to}"))
+        .collect()
+}
+
+/// A `Service` has no direct pointer to the `Container` it checks, but both
+/// reference the same `Port` label, so a container's healthcheck is the
+/// service whose `port` is one of that container's exposed port labels.
+fn healthcheck_for(container: &Container, group: &Group) -> Option<ComposeHealthcheck> {
+    group
+        .services
+        .iter()
+        .find(|svc: &&Service| container.ports.iter().any(|p| p == &svc.port))
+        .map(|svc| {
+            let test = match resolve_port(&svc.port, group) {
+                Some(port) => vec![
+                    "CMD".into(),
+                    "curl".into(),
+                    "-f".into(),
+                    format!("http://localhost:{port}{}", svc.check.path),
+                ],
+                None => vec!["CMD".into(), "curl".into(), "-f".into(), svc.check.path.clone()],
+            };
+            ComposeHealthcheck {
+                test,
+                interval: svc.check.interval.clone(),
+                timeout: svc.check.timeout.clone(),
+            }
+        })
+}
+
+fn compose_service(container: &Container, group: &Group) -> ComposeService {
+    let mut volumes = vec![];
+    for mount in &container.mounts {
+        volumes.push(format!("{}:{}", mount.volume, mount.mountpoint));
+    }
+
+    let mut environment = BTreeMap::new();
+    for e in &container.env {
+        environment.insert(e.name.clone(), env_value_to_string(&e.val));
+    }
+
+    ComposeService {
+        image: container.image.clone(),
+        ports: compose_ports(container, group),
+        volumes,
+        environment,
+        healthcheck: healthcheck_for(container, group),
+    }
+}
+
+/// Flattens every job/group's containers and volumes into a single
+/// docker-compose file. Compose has no notion of jobs or groups, so
+/// container names are expected to be unique across the whole `JobConfig`.
+pub fn job_to_compose(cfg: &JobConfig) -> ComposeFile {
+    let mut services = BTreeMap::new();
+    let mut volumes = BTreeMap::new();
+
+    for job in &cfg.jobs {
+        for group in job.resolved_groups() {
+            for container in &group.containers {
+                services.insert(container.name.clone(), compose_service(container, &group));
+            }
+            for vol in &group.volumes {
+                volumes.insert(vol.name.clone(), ComposeVolume {});
+            }
+        }
+    }
+
+    ComposeFile {
+        version: "3.8".to_string(),
+        services,
+        volumes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContainerMount, EnvEntry, Port, ServiceCheck};
+
+    fn group_with_http_service() -> Group {
+        Group {
+            name: "web".into(),
+            count: 1,
+            ports: vec![Port {
+                name: "http".into(),
+                to: 8080,
+            }],
+            services: vec![Service {
+                name: "web".into(),
+                port: "http".into(),
+                tags: vec![],
+                check: ServiceCheck {
+                    check_type: "http".into(),
+                    interval: "15s".into(),
+                    timeout: "3s".into(),
+                    path: "/health".into(),
+                },
+            }],
+            containers: vec![],
+            volumes: vec![],
+        }
+    }
+
+    fn container_with_ports(ports: Vec<&str>) -> Container {
+        Container {
+            name: "web".into(),
+            image: "nginx:1.25".into(),
+            ports: ports.into_iter().map(String::from).collect(),
+            mounts: vec![ContainerMount {
+                volume: "data".into(),
+                mountpoint: "/data".into(),
+            }],
+            env: vec![EnvEntry {
+                name: "MODE".into(),
+                val: toml::Value::String("prod".into()),
+            }],
+        }
+    }
+
+    #[test]
+    fn compose_ports_resolves_labels_to_host_container_mappings() {
+        let group = group_with_http_service();
+        let container = container_with_ports(vec!["http"]);
+
+        assert_eq!(compose_ports(&container, &group), vec!["8080:8080".to_string()]);
+    }
+
+    #[test]
+    fn compose_ports_skips_unresolvable_labels() {
+        let group = group_with_http_service();
+        let container = container_with_ports(vec!["missing"]);
+
+        assert!(compose_ports(&container, &group).is_empty());
+    }
+
+    #[test]
+    fn healthcheck_for_matches_service_via_shared_port_label() {
+        let group = group_with_http_service();
+        let container = container_with_ports(vec!["http"]);
+
+        let healthcheck = healthcheck_for(&container, &group).unwrap();
+        assert_eq!(
+            healthcheck.test,
+            vec!["CMD", "curl", "-f", "http://localhost:8080/health"]
+        );
+        assert_eq!(healthcheck.interval, "15s");
+        assert_eq!(healthcheck.timeout, "3s");
+    }
+
+    #[test]
+    fn healthcheck_for_is_none_when_no_service_shares_a_port() {
+        let group = group_with_http_service();
+        let container = container_with_ports(vec!["other"]);
+
+        assert!(healthcheck_for(&container, &group).is_none());
+    }
+
+    #[test]
+    fn compose_service_maps_mounts_and_environment() {
+        let group = group_with_http_service();
+        let container = container_with_ports(vec!["http"]);
+
+        let service = compose_service(&container, &group);
+        assert_eq!(service.volumes, vec!["data:/data".to_string()]);
+        assert_eq!(service.environment.get("MODE"), Some(&"prod".to_string()));
+    }
+}
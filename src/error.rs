@@ -0,0 +1,42 @@
+use thiserror::Error;
+
+/// Errors produced while converting between the TOML `JobConfig` schema and
+/// its various output/input formats.
+#[derive(Debug, Error)]
+pub enum TomnoError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid TOML: {0}")]
+    TomlDecode(#[from] toml::de::Error),
+
+    #[error("failed to serialize TOML: {0}")]
+    TomlEncode(#[from] toml::ser::Error),
+
+    #[error("invalid HCL: {0}")]
+    Hcl(#[from] hcl::Error),
+
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to serialize compose output: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("unknown volume access mode: {0}")]
+    UnknownAccessMode(String),
+
+    #[error("unknown variable reference: ${{{0}}}")]
+    UnknownVariable(String),
+
+    #[error("cyclic variable reference: ${{var.{0}}}")]
+    CyclicVariable(String),
+
+    #[error("malformed HCL input: {0}")]
+    MalformedHcl(String),
+
+    #[error("docker daemon request failed: {0}")]
+    Docker(String),
+
+    #[error("invalid command line arguments: {0}")]
+    Cli(String),
+}
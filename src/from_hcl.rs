@@ -0,0 +1,301 @@
+use hcl::{Body, Expression};
+
+use crate::{
+    Container, ContainerMount, EnvEntry, General, Group, Job, JobConfig, Port, Service,
+    ServiceCheck, TomnoError, Volume,
+};
+
+fn get_access_mode_short(s: &str) -> Result<String, TomnoError> {
+    match s {
+        "multi-node-multi-writer" => Ok("mnmw".into()),
+        "multi-node-single-writer" => Ok("mnsw".into()),
+        _ => Err(TomnoError::UnknownAccessMode(s.to_string())),
+    }
+}
+
+fn expr_to_string(expr: &Expression) -> String {
+    match expr {
+        Expression::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn expr_to_strings(expr: &Expression) -> Vec<String> {
+    match expr {
+        Expression::Array(items) => items.iter().map(expr_to_string).collect(),
+        other => vec![expr_to_string(other)],
+    }
+}
+
+/// Converts an `env` attribute's HCL expression back to a `toml::Value`,
+/// preserving the original type so ints/bools round-trip instead of
+/// flattening everything to a string.
+fn expr_to_env_value(expr: &Expression) -> toml::Value {
+    match expr {
+        Expression::Bool(b) => toml::Value::Boolean(*b),
+        Expression::Number(n) => n
+            .as_i64()
+            .map(toml::Value::Integer)
+            .or_else(|| n.as_f64().map(toml::Value::Float))
+            .unwrap_or_else(|| toml::Value::String(n.to_string())),
+        other => toml::Value::String(expr_to_string(other)),
+    }
+}
+
+fn attr<'a>(body: &'a Body, key: &str) -> Option<&'a Expression> {
+    body.attributes().find(|a| a.key() == key).map(|a| a.expr())
+}
+
+fn attr_str(body: &Body, key: &str) -> Option<String> {
+    attr(body, key).map(expr_to_string)
+}
+
+fn blocks<'a>(body: &'a Body, ident: &'a str) -> impl Iterator<Item = &'a hcl::Block> {
+    body.blocks().filter(move |b| b.identifier() == ident)
+}
+
+fn port_from_block(block: &hcl::Block) -> Port {
+    Port {
+        name: block.labels()[0].as_str().to_string(),
+        to: attr_str(block.body(), "to")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default(),
+    }
+}
+
+fn service_check_from_block(block: &hcl::Block) -> ServiceCheck {
+    let body = block.body();
+    ServiceCheck {
+        check_type: attr_str(body, "type").unwrap_or_default(),
+        interval: attr_str(body, "interval").unwrap_or_else(|| "15s".to_string()),
+        timeout: attr_str(body, "timeout").unwrap_or_else(|| "3s".to_string()),
+        path: attr_str(body, "path").unwrap_or_default(),
+    }
+}
+
+fn service_from_block(block: &hcl::Block) -> Result<Service, TomnoError> {
+    let body = block.body();
+    let check = blocks(body, "check")
+        .next()
+        .map(service_check_from_block)
+        .ok_or_else(|| TomnoError::MalformedHcl("service block missing check".to_string()))?;
+
+    Ok(Service {
+        name: attr_str(body, "name").unwrap_or_default(),
+        port: attr_str(body, "port").unwrap_or_default(),
+        tags: attr(body, "tags").map(expr_to_strings).unwrap_or_default(),
+        check,
+    })
+}
+
+fn volume_from_block(block: &hcl::Block) -> Result<Volume, TomnoError> {
+    let body = block.body();
+    let access_mode = match attr_str(body, "access_mode") {
+        Some(mode) => get_access_mode_short(&mode)?,
+        None => String::new(),
+    };
+
+    Ok(Volume {
+        name: block.labels()[0].as_str().to_string(),
+        access_mode,
+        read_only: attr_str(body, "read_only")
+            .map(|s| s == "true")
+            .unwrap_or(false),
+    })
+}
+
+fn container_from_block(block: &hcl::Block) -> Result<Container, TomnoError> {
+    let body = block.body();
+    let config = blocks(body, "config")
+        .next()
+        .ok_or_else(|| TomnoError::MalformedHcl("task block missing config".to_string()))?;
+    let config_body = config.body();
+
+    let mounts = blocks(body, "volume_mount")
+        .map(|m| ContainerMount {
+            volume: attr_str(m.body(), "volume").unwrap_or_default(),
+            mountpoint: attr_str(m.body(), "destination").unwrap_or_default(),
+        })
+        .collect();
+
+    let env = blocks(body, "env")
+        .next()
+        .map(|e| {
+            e.body()
+                .attributes()
+                .map(|a| EnvEntry {
+                    name: a.key().to_string(),
+                    val: expr_to_env_value(a.expr()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Container {
+        name: block.labels()[0].as_str().to_string(),
+        image: attr_str(config_body, "image").unwrap_or_default(),
+        ports: attr(config_body, "ports")
+            .map(expr_to_strings)
+            .unwrap_or_default(),
+        mounts,
+        env,
+    })
+}
+
+fn group_from_block(block: &hcl::Block) -> Result<Group, TomnoError> {
+    let body = block.body();
+
+    let network_body = blocks(body, "network")
+        .next()
+        .map(|b| b.body())
+        .ok_or_else(|| {
+            TomnoError::MalformedHcl("no network block found in HCL input".to_string())
+        })?;
+
+    let ports = blocks(network_body, "port").map(port_from_block).collect();
+    let services = blocks(body, "service")
+        .map(service_from_block)
+        .collect::<Result<_, _>>()?;
+    let volumes = blocks(body, "volume")
+        .map(volume_from_block)
+        .collect::<Result<_, _>>()?;
+    let containers = blocks(body, "task")
+        .map(container_from_block)
+        .collect::<Result<_, _>>()?;
+
+    Ok(Group {
+        name: block.labels()[0].as_str().to_string(),
+        count: attr_str(body, "count")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1),
+        ports,
+        services,
+        containers,
+        volumes,
+    })
+}
+
+fn job_from_block(block: &hcl::Block) -> Result<Job, TomnoError> {
+    let body = block.body();
+
+    let general = General {
+        name: block.labels()[0].as_str().to_string(),
+        datacenters: attr(body, "datacenters")
+            .map(expr_to_strings)
+            .unwrap_or_default(),
+    };
+
+    let groups = blocks(body, "group")
+        .map(group_from_block)
+        .collect::<Result<Vec<_>, _>>()?;
+    if groups.is_empty() {
+        return Err(TomnoError::MalformedHcl(
+            "no group block found in HCL input".to_string(),
+        ));
+    }
+
+    Ok(Job {
+        general,
+        groups,
+        count: 0,
+        ports: vec![],
+        services: vec![],
+        containers: vec![],
+        volumes: vec![],
+    })
+}
+
+/// Parses a Nomad job specification back into a [`JobConfig`], inverting
+/// the shape produced by [`crate::job_to_hcl`]. Each `job` block in `raw`
+/// becomes one entry in `jobs`, and each `group` block within it becomes
+/// one entry in that job's `groups`.
+pub fn job_from_hcl(raw: &str) -> Result<JobConfig, TomnoError> {
+    let body: Body = hcl::from_str(raw)?;
+    let jobs = blocks(&body, "job")
+        .map(job_from_block)
+        .collect::<Result<Vec<_>, _>>()?;
+    if jobs.is_empty() {
+        return Err(TomnoError::MalformedHcl(
+            "no job block found in HCL input".to_string(),
+        ));
+    }
+
+    Ok(JobConfig { jobs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Container, EnvEntry, General, Group, Job, Service, ServiceCheck};
+
+    fn sample_config() -> JobConfig {
+        JobConfig {
+            jobs: vec![Job {
+                general: General {
+                    name: "web".into(),
+                    datacenters: vec!["dc1".into()],
+                },
+                groups: vec![Group {
+                    name: "web".into(),
+                    count: 2,
+                    ports: vec![Port {
+                        name: "http".into(),
+                        to: 8080,
+                    }],
+                    services: vec![Service {
+                        name: "web".into(),
+                        port: "http".into(),
+                        tags: vec![],
+                        check: ServiceCheck {
+                            check_type: "http".into(),
+                            interval: "15s".into(),
+                            timeout: "3s".into(),
+                            path: "/health".into(),
+                        },
+                    }],
+                    containers: vec![Container {
+                        name: "web".into(),
+                        image: "nginx:1.25".into(),
+                        ports: vec!["http".into()],
+                        mounts: vec![],
+                        env: vec![
+                            EnvEntry {
+                                name: "REPLICAS".into(),
+                                val: toml::Value::Integer(3),
+                            },
+                            EnvEntry {
+                                name: "MODE".into(),
+                                val: toml::Value::String("prod".into()),
+                            },
+                        ],
+                    }],
+                    volumes: vec![],
+                }],
+                count: 0,
+                ports: vec![],
+                services: vec![],
+                containers: vec![],
+                volumes: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn round_trips_env_value_types() {
+        let cfg = sample_config();
+        let hcl = crate::job_to_hcl(&cfg).unwrap();
+        let parsed = job_from_hcl(&hcl).unwrap();
+
+        let env = &parsed.jobs[0].groups[0].containers[0].env;
+        let replicas = env.iter().find(|e| e.name == "REPLICAS").unwrap();
+        assert_eq!(replicas.val, toml::Value::Integer(3));
+        let mode = env.iter().find(|e| e.name == "MODE").unwrap();
+        assert_eq!(mode.val, toml::Value::String("prod".to_string()));
+    }
+
+    #[test]
+    fn errors_on_missing_job_block() {
+        let err = job_from_hcl("").unwrap_err();
+        assert!(matches!(err, TomnoError::MalformedHcl(_)));
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+use toml::value::Table;
+use toml::Value;
+
+use crate::TomnoError;
+
+enum Placeholder {
+    Var(String),
+    Env(String),
+}
+
+fn parse_placeholder(inner: &str) -> Result<Placeholder, TomnoError> {
+    if let Some(name) = inner.strip_prefix("var.") {
+        Ok(Placeholder::Var(name.to_string()))
+    } else if let Some(name) = inner.strip_prefix("env.") {
+        Ok(Placeholder::Env(name.to_string()))
+    } else {
+        Err(TomnoError::UnknownVariable(inner.to_string()))
+    }
+}
+
+/// Finds `${...}` spans in `s`, returning `(start, end)` byte ranges that
+/// include the surrounding `${` and `}`.
+fn find_placeholders(s: &str) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    let mut rest = s;
+    let mut offset = 0;
+    while let Some(start) = rest.find("${") {
+        if let Some(len) = rest[start..].find('}') {
+            let end = start + len + 1;
+            spans.push((offset + start, offset + end));
+            offset += end;
+            rest = &rest[end..];
+        } else {
+            break;
+        }
+    }
+    spans
+}
+
+fn resolve_placeholder(
+    placeholder: &Placeholder,
+    variables: &Table,
+    cache: &mut HashMap<String, Value>,
+    visiting: &mut HashSet<String>,
+) -> Result<Value, TomnoError> {
+    match placeholder {
+        Placeholder::Var(name) => resolve_var(name, variables, cache, visiting),
+        Placeholder::Env(name) => std::env::var(name)
+            .map(Value::String)
+            .map_err(|_| TomnoError::UnknownVariable(format!("env.{name}"))),
+    }
+}
+
+fn resolve_var(
+    name: &str,
+    variables: &Table,
+    cache: &mut HashMap<String, Value>,
+    visiting: &mut HashSet<String>,
+) -> Result<Value, TomnoError> {
+    if let Some(resolved) = cache.get(name) {
+        return Ok(resolved.clone());
+    }
+    if visiting.contains(name) {
+        return Err(TomnoError::CyclicVariable(name.to_string()));
+    }
+    let raw = variables
+        .get(name)
+        .cloned()
+        .ok_or_else(|| TomnoError::UnknownVariable(format!("var.{name}")))?;
+
+    visiting.insert(name.to_string());
+    let resolved = match raw {
+        Value::String(s) => substitute_string(&s, variables, cache, visiting)?,
+        other => other,
+    };
+    visiting.remove(name);
+
+    cache.insert(name.to_string(), resolved.clone());
+    Ok(resolved)
+}
+
+/// Expands every `${...}` placeholder in `s`. When the whole string is a
+/// single placeholder, the resolved value's own type (integer, bool, ...)
+/// is preserved; otherwise the resolved values are stringified and spliced
+/// into the surrounding text.
+fn substitute_string(
+    s: &str,
+    variables: &Table,
+    cache: &mut HashMap<String, Value>,
+    visiting: &mut HashSet<String>,
+) -> Result<Value, TomnoError> {
+    let spans = find_placeholders(s);
+    if spans.is_empty() {
+        return Ok(Value::String(s.to_string()));
+    }
+
+    if spans.len() == 1 && spans[0] == (0, s.len()) {
+        let inner = &s[2..s.len() - 1];
+        let placeholder = parse_placeholder(inner)?;
+        return resolve_placeholder(&placeholder, variables, cache, visiting);
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut cursor = 0;
+    for (start, end) in spans {
+        out.push_str(&s[cursor..start]);
+        let inner = &s[start + 2..end - 1];
+        let placeholder = parse_placeholder(inner)?;
+        let resolved = resolve_placeholder(&placeholder, variables, cache, visiting)?;
+        out.push_str(&display_value(&resolved));
+        cursor = end;
+    }
+    out.push_str(&s[cursor..]);
+    Ok(Value::String(out))
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn walk(value: &mut Value, variables: &Table, cache: &mut HashMap<String, Value>) -> Result<(), TomnoError> {
+    match value {
+        Value::String(s) => {
+            *value = substitute_string(s, variables, cache, &mut HashSet::new())?;
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                walk(item, variables, cache)?;
+            }
+        }
+        Value::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                walk(item, variables, cache)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Parses `raw` as TOML and resolves `${var.name}` / `${env.NAME}`
+/// placeholders against the top-level `[variables]` table and the process
+/// environment, returning the expanded `toml::Value` tree.
+pub fn resolve(raw: &str) -> Result<Value, TomnoError> {
+    let mut root: Value = toml::from_str(raw)?;
+    let variables = match root.get("variables") {
+        Some(Value::Table(table)) => table.clone(),
+        _ => Table::new(),
+    };
+
+    let mut cache = HashMap::new();
+    walk(&mut root, &variables, &mut cache)?;
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_string_placeholder_preserves_type() {
+        let raw = r#"
+            [variables]
+            count = 3
+
+            [x]
+            y = "${var.count}"
+        "#;
+        let resolved = resolve(raw).unwrap();
+        assert_eq!(resolved["x"]["y"], Value::Integer(3));
+    }
+
+    #[test]
+    fn spliced_placeholder_stays_a_string() {
+        let raw = r#"
+            [variables]
+            name = "nginx"
+
+            [x]
+            y = "image-${var.name}-final"
+        "#;
+        let resolved = resolve(raw).unwrap();
+        assert_eq!(resolved["x"]["y"], Value::String("image-nginx-final".to_string()));
+    }
+
+    #[test]
+    fn unknown_variable_errors() {
+        let raw = r#"
+            [x]
+            y = "${var.missing}"
+        "#;
+        let err = resolve(raw).unwrap_err();
+        assert!(matches!(err, TomnoError::UnknownVariable(name) if name == "var.missing"));
+    }
+
+    #[test]
+    fn cyclic_variable_errors() {
+        let raw = r#"
+            [variables]
+            a = "${var.b}"
+            b = "${var.a}"
+
+            [x]
+            y = "${var.a}"
+        "#;
+        let err = resolve(raw).unwrap_err();
+        assert!(matches!(err, TomnoError::CyclicVariable(_)));
+    }
+}
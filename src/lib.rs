@@ -0,0 +1,376 @@
+#![allow(dead_code)]
+
+pub mod compose;
+mod error;
+pub mod from_hcl;
+pub mod interpolate;
+pub mod pin;
+
+pub use error::TomnoError;
+
+use serde_derive::{Deserialize, Serialize};
+
+use hcl::{Attribute, Block, Body};
+use toml::Value;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JobConfig {
+    pub jobs: Vec<Job>,
+}
+
+impl JobConfig {
+    /// Mutable access to every container across every job/group, used by
+    /// `--pin` to rewrite image tags in place.
+    pub fn containers_mut(&mut self) -> impl Iterator<Item = &mut Container> {
+        self.jobs.iter_mut().flat_map(|j| j.containers_mut())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Job {
+    pub general: General,
+    #[serde(default)]
+    pub groups: Vec<Group>,
+    // Flattened single-group layout, used as the lone group when `groups`
+    // is empty. These fields live inside each `[[jobs]]` entry: a file
+    // without `jobs` is rejected, and `count` is read here, not from `general`.
+    pub count: u16,
+    #[serde(default)]
+    pub ports: Vec<Port>,
+    #[serde(default)]
+    pub services: Vec<Service>,
+    #[serde(default)]
+    pub containers: Vec<Container>,
+    #[serde(default)]
+    pub volumes: Vec<Volume>,
+}
+
+impl Job {
+    /// Returns this job's groups, falling back to a single default group
+    /// (named after the job) built from the flattened top-level fields
+    /// when no `[[groups]]` were given.
+    pub fn resolved_groups(&self) -> Vec<Group> {
+        if !self.groups.is_empty() {
+            return self.groups.clone();
+        }
+
+        vec![Group {
+            name: self.general.name.clone(),
+            count: self.count,
+            ports: self.ports.clone(),
+            services: self.services.clone(),
+            containers: self.containers.clone(),
+            volumes: self.volumes.clone(),
+        }]
+    }
+
+    /// Mutable access to every container across this job's groups (or its
+    /// flattened top-level containers when `[[groups]]` is absent), used by
+    /// `--pin` to rewrite image tags in place.
+    fn containers_mut(&mut self) -> Box<dyn Iterator<Item = &mut Container> + '_> {
+        if self.groups.is_empty() {
+            Box::new(self.containers.iter_mut())
+        } else {
+            Box::new(self.groups.iter_mut().flat_map(|g| g.containers.iter_mut()))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Group {
+    pub name: String,
+    pub count: u16,
+    #[serde(default)]
+    pub ports: Vec<Port>,
+    #[serde(default)]
+    pub services: Vec<Service>,
+    #[serde(default)]
+    pub containers: Vec<Container>,
+    #[serde(default)]
+    pub volumes: Vec<Volume>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct General {
+    pub name: String,
+    pub datacenters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Port {
+    pub name: String,
+    pub to: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Service {
+    pub name: String,
+    pub port: String,
+    pub tags: Vec<String>,
+    pub check: ServiceCheck,
+}
+
+fn default_interval() -> String {
+    "15s".to_string()
+}
+
+fn default_timeout() -> String {
+    "3s".to_string()
+}
+
+fn default_false() -> bool {
+    false
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServiceCheck {
+    #[serde(rename = "type")]
+    pub check_type: String,
+    #[serde(default = "default_interval")]
+    pub interval: String,
+    #[serde(default = "default_timeout")]
+    pub timeout: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Container {
+    pub name: String,
+    pub image: String,
+    pub ports: Vec<String>,
+    pub mounts: Vec<ContainerMount>,
+    pub env: Vec<EnvEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EnvEntry {
+    pub name: String,
+    pub val: Value,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContainerMount {
+    pub volume: String,
+    pub mountpoint: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Volume {
+    pub name: String,
+    #[serde(rename = "accessMode")]
+    pub access_mode: String,
+    #[serde(rename = "readOnly")]
+    #[serde(default = "default_false")]
+    pub read_only: bool,
+}
+
+pub(crate) fn get_vol_access_mode(s: &str) -> Result<String, TomnoError> {
+    match s {
+        "mnmw" => Ok("multi-node-multi-writer".into()),
+        "mnsw" => Ok("multi-node-single-writer".into()),
+        _ => Err(TomnoError::UnknownAccessMode(s.to_string())),
+    }
+}
+
+/// Parses `raw` TOML, resolving `${var.*}`/`${env.*}` placeholders first,
+/// into a [`JobConfig`].
+pub fn parse_job(raw: &str) -> Result<JobConfig, TomnoError> {
+    let resolved = interpolate::resolve(raw)?;
+    Ok(resolved.try_into()?)
+}
+
+fn group_to_hcl_block(group: &Group) -> Result<Block, TomnoError> {
+    let mut port_blocks: Vec<Block> = vec![];
+    for port in &group.ports {
+        port_blocks.push(
+            Block::builder("port")
+                .add_label(port.name.clone())
+                .add_attribute(("to", port.to))
+                .build(),
+        );
+    }
+
+    let mut service_blocks: Vec<Block> = vec![];
+    for svc in &group.services {
+        service_blocks.push(
+            Block::builder("service")
+                .add_attribute(("name", svc.name.clone()))
+                .add_attribute(("port", svc.port.clone()))
+                .add_attribute(("tags", svc.tags.clone()))
+                .add_block(
+                    Block::builder("check")
+                        .add_attribute(("type", svc.check.check_type.clone()))
+                        .add_attribute(("path", svc.check.path.clone()))
+                        .add_attribute(("name", "app_health"))
+                        .add_attribute(("interval", svc.check.interval.clone()))
+                        .add_attribute(("timeout", svc.check.timeout.clone()))
+                        .build(),
+                )
+                .build(),
+        )
+    }
+
+    let mut volume_blocks: Vec<Block> = vec![];
+    for vol in &group.volumes {
+        volume_blocks.push(
+            Block::builder("volume")
+                .add_label(&vol.name)
+                .add_attribute(("type", "csi"))
+                .add_attribute(("source", vol.name.clone()))
+                .add_attribute(("access_mode", get_vol_access_mode(&vol.access_mode)?))
+                .add_attribute(("read_only", vol.read_only))
+                .add_attribute(("attachment_mode", "filesystem"))
+                .build(),
+        )
+    }
+
+    let mut task_blocks: Vec<Block> = vec![];
+    for container in &group.containers {
+        let mut mounts_blocks = vec![];
+        for vol in &container.mounts {
+            mounts_blocks.push(
+                Block::builder("volume_mount")
+                    .add_attribute(("volume", vol.volume.clone()))
+                    .add_attribute(("destination", vol.mountpoint.clone()))
+                    .build(),
+            )
+        }
+        let mut env_block_attributes = vec![];
+        for e in &container.env {
+            if e.val.is_integer() {
+                env_block_attributes.push(Attribute::new(e.name.clone(), e.val.as_integer().unwrap()))
+            } else if e.val.is_str() {
+                env_block_attributes.push(Attribute::new(e.name.clone(), e.val.as_str().unwrap()))
+            }
+        }
+
+        task_blocks.push(
+            Block::builder("task")
+                .add_label(container.name.clone())
+                .add_attribute(("driver", "docker"))
+                .add_block(
+                    Block::builder("config")
+                        .add_attribute(("image", container.image.clone()))
+                        .add_attribute(("ports", container.ports.clone()))
+                        .build(),
+                )
+                .add_blocks(mounts_blocks.into_iter())
+                .add_block(
+                    Block::builder("restart")
+                        .add_attribute(("attempts", 3))
+                        .add_attribute(("delay", "20s"))
+                        .build(),
+                )
+                .add_block(
+                    Block::builder("env")
+                        .add_attributes(env_block_attributes.into_iter())
+                        .build(),
+                )
+                .build(),
+        )
+    }
+
+    Ok(Block::builder("group")
+        .add_label(&group.name)
+        .add_attribute(("count", group.count))
+        .add_block(
+            Block::builder("network")
+                .add_blocks(port_blocks.into_iter())
+                .build(),
+        )
+        .add_blocks(service_blocks.into_iter())
+        .add_blocks(volume_blocks.into_iter())
+        .add_blocks(task_blocks.into_iter())
+        .build())
+}
+
+fn job_to_hcl_block(job: &Job) -> Result<Block, TomnoError> {
+    let group_blocks: Vec<Block> = job
+        .resolved_groups()
+        .iter()
+        .map(group_to_hcl_block)
+        .collect::<Result<_, _>>()?;
+
+    Ok(Block::builder("job")
+        .add_label(&job.general.name)
+        .add_attribute(("datacenters", job.general.datacenters.clone()))
+        .add_blocks(group_blocks.into_iter())
+        .build())
+}
+
+/// Converts a [`JobConfig`] into a Nomad HCL job specification, emitting
+/// one `job` block per entry in `jobs` and one `group` block per entry in
+/// each job's resolved groups.
+pub fn job_to_hcl(cfg: &JobConfig) -> Result<String, TomnoError> {
+    let job_blocks: Vec<Block> = cfg
+        .jobs
+        .iter()
+        .map(job_to_hcl_block)
+        .collect::<Result<_, _>>()?;
+
+    let body = Body::builder().add_blocks(job_blocks.into_iter()).build();
+    Ok(hcl::to_string(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minimal_group(name: &str) -> Group {
+        Group {
+            name: name.to_string(),
+            count: 1,
+            ports: vec![],
+            services: vec![],
+            containers: vec![],
+            volumes: vec![],
+        }
+    }
+
+    fn flattened_job(name: &str, count: u16) -> Job {
+        Job {
+            general: General {
+                name: name.to_string(),
+                datacenters: vec!["dc1".to_string()],
+            },
+            groups: vec![],
+            count,
+            ports: vec![],
+            services: vec![],
+            containers: vec![],
+            volumes: vec![],
+        }
+    }
+
+    #[test]
+    fn resolved_groups_falls_back_to_flattened_layout_when_empty() {
+        let job = flattened_job("web", 3);
+        let groups = job.resolved_groups();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "web");
+        assert_eq!(groups[0].count, 3);
+    }
+
+    #[test]
+    fn job_to_hcl_emits_independent_blocks_per_job_and_group() {
+        let mut worker = flattened_job("worker", 1);
+        worker.groups = vec![minimal_group("fetchers"), minimal_group("crunchers")];
+
+        let cfg = JobConfig {
+            jobs: vec![flattened_job("web", 2), worker],
+        };
+
+        let hcl = job_to_hcl(&cfg).unwrap();
+
+        assert_eq!(hcl.matches("job \"web\"").count(), 1);
+        assert_eq!(hcl.matches("job \"worker\"").count(), 1);
+        assert_eq!(hcl.matches("group \"web\"").count(), 1);
+        assert_eq!(hcl.matches("group \"fetchers\"").count(), 1);
+        assert_eq!(hcl.matches("group \"crunchers\"").count(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+
+use crate::TomnoError;
+
+const DOCKER_SOCK: &str = "/var/run/docker.sock";
+
+/// Splits `nginx:1.25` into (`nginx`, `1.25`), defaulting the tag to
+/// `latest` when none is given.
+fn split_tag(image: &str) -> (&str, &str) {
+    match image.rsplit_once(':') {
+        // a ':' before the last '/' is a registry port, not a tag
+        Some((repo, tag)) if !tag.contains('/') => (repo, tag),
+        _ => (image, "latest"),
+    }
+}
+
+fn docker_get(path: &str) -> Result<String, TomnoError> {
+    let mut stream = UnixStream::connect(DOCKER_SOCK)?;
+    let request = format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw)?;
+
+    raw.split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .ok_or_else(|| TomnoError::Docker("malformed response from docker daemon".to_string()))
+}
+
+/// Resolves `image` (e.g. `nginx:1.25`) to an immutable `repo@sha256:...`
+/// digest by inspecting it via the Docker Engine API over the unix socket,
+/// mirroring what `docker inspect` reads from `RepoDigests`.
+pub fn resolve_digest(image: &str) -> Result<String, TomnoError> {
+    let (repo, _tag) = split_tag(image);
+    let body = docker_get(&format!("/images/{image}/json"))?;
+    let parsed: serde_json::Value = serde_json::from_str(&body)?;
+
+    let digests = parsed
+        .get("RepoDigests")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| TomnoError::Docker(format!("no RepoDigests for image {image}")))?;
+
+    digests
+        .iter()
+        .filter_map(|d| d.as_str())
+        .find(|d| d.starts_with(repo))
+        .map(str::to_string)
+        .ok_or_else(|| TomnoError::Docker(format!("no matching digest for image {image}")))
+}
+
+pub fn write_lockfile(path: &str, digests: &BTreeMap<String, String>) -> Result<(), TomnoError> {
+    let lock_path = format!("{path}.lock");
+    let contents = toml::to_string_pretty(digests)?;
+    std::fs::write(lock_path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_tag_splits_on_tag() {
+        assert_eq!(split_tag("nginx:1.25"), ("nginx", "1.25"));
+    }
+
+    #[test]
+    fn split_tag_defaults_bare_image_to_latest() {
+        assert_eq!(split_tag("nginx"), ("nginx", "latest"));
+    }
+
+    #[test]
+    fn split_tag_treats_registry_port_as_not_a_tag() {
+        assert_eq!(
+            split_tag("registry.internal:5000/nginx"),
+            ("registry.internal:5000/nginx", "latest")
+        );
+    }
+
+    #[test]
+    fn split_tag_splits_registry_port_image_with_explicit_tag() {
+        assert_eq!(
+            split_tag("registry.internal:5000/nginx:1.25"),
+            ("registry.internal:5000/nginx", "1.25")
+        );
+    }
+}